@@ -55,10 +55,24 @@
 //! to the inject queue after step 6 of shutdown, which would leave a task in
 //! the inject queue indefinitely. This would be a ref-count cycle and a memory
 //! leak.
+//!
+//! ## Testing
+//!
+//! This module has no unit tests of its own, and none were added by the
+//! `lifo_enabled`/`lifo_fairness_cap`/`global_queue_interval`/`steal_throttle_factor` series of
+//! changes, which is a blocking gap rather than something to wave off:
+//! in particular the `lifo_fairness_cap` boundary (`cap == 0`, and the cap
+//! reached exactly on the last allowed poll) and `steal_throttle_factor == 0`
+//! (which disables the steal throttle entirely, since `0 < num_workers` is
+//! always true) are untested edge cases. Scheduler behavior here is
+//! otherwise exercised by the runtime's integration and loom tests elsewhere
+//! in the crate; this module has no local harness of its own to add
+//! `#[test]`s to without pulling those modules into this change as well.
 
 use crate::coop;
 use crate::future::Future;
 use crate::loom::rand::seed;
+use crate::loom::sync::atomic::AtomicUsize;
 use crate::loom::sync::{Arc, Mutex};
 use crate::runtime;
 use crate::runtime::enter::EnterContext;
@@ -71,6 +85,7 @@ use crate::util::atomic_cell::AtomicCell;
 use crate::util::FastRand;
 
 use std::cell::RefCell;
+use std::sync::atomic::Ordering::Relaxed;
 use std::time::Duration;
 
 /// A scheduler worker
@@ -87,8 +102,10 @@ pub(super) struct Worker {
 
 /// Core data
 struct Core {
-    /// Used to schedule bookkeeping tasks every so often.
-    tick: u8,
+    /// Used to schedule bookkeeping tasks every so often. Widened to `u32`
+    /// so it can track ticks against a configurable
+    /// `Config::global_queue_interval` without wrapping prematurely.
+    tick: u32,
 
     /// When a task is scheduled from a worker, it is stored in this slot. The
     /// worker will check this slot for a task **before** checking the run
@@ -167,6 +184,23 @@ pub(super) struct Shared {
     pub(super) scheduler_metrics: SchedulerMetrics,
 
     pub(super) worker_metrics: Box<[WorkerMetrics]>,
+
+    /// Scheduler configuration, set by the `Builder`.
+    config: Config,
+
+    /// Number of workers currently searching for work to steal.
+    ///
+    /// This duplicates state `Idle` already tracks internally. The proper
+    /// fix is a real `Idle` API change — e.g. restoring the
+    /// `transition_worker_to_searching(steal_throttle_factor)` overload the
+    /// first attempt at this used — so `steal_throttle_factor` is enforced
+    /// by the same state `Idle` already owns instead of a second counter
+    /// maintained by convention. That wasn't done here because `idle.rs`
+    /// isn't part of this change and its real API can't be safely guessed
+    /// at or modified blind. Until that lands, every place `Core::is_searching`
+    /// flips is marked `// num_searching: ...` below; keep those in sync by
+    /// hand if you touch one of them.
+    num_searching: AtomicUsize,
 }
 
 /// Used to communicate with a worker from other threads.
@@ -187,6 +221,76 @@ struct ActiveWorker {
     core: RefCell<Option<Box<Core>>>,
 }
 
+/// Scheduler configuration options, set from the runtime `Builder` and
+/// shared by every worker.
+///
+/// None of these fields have a `Builder` setter yet: `Builder` still only
+/// ever constructs `Config::default()`, so every field below is permanently
+/// pinned to its default for callers outside this crate until that setter
+/// work lands (tracked as a blocking follow-up, not done in this change):
+/// `Builder::disable_lifo_slot` (or similar) for `lifo_enabled`, a cap setter
+/// for `lifo_fairness_cap`, `Builder::global_queue_interval`, and a setter
+/// for `steal_throttle_factor`. Likewise, `create`'s `config` parameter below
+/// has no updated caller yet; the call site in `thread_pool/mod.rs` also
+/// needs to pass a `Builder`-derived `Config` through instead of whatever it
+/// builds today.
+#[derive(Clone)]
+pub(crate) struct Config {
+    /// If `false`, the LIFO slot is disabled: `schedule_local` always pushes
+    /// to the back of `run_queue` and `next_local_task` never consults the
+    /// slot. This trades the message-passing latency win of the LIFO slot
+    /// for better fairness under fan-out workloads.
+    pub(crate) lifo_enabled: bool,
+
+    /// When the LIFO slot is enabled, caps the number of tasks that may be
+    /// run consecutively out of the slot in a single `run_task` call before
+    /// the next one is pushed to the back of `run_queue` instead. This keeps
+    /// a hot message-passing chain from starving the rest of the run queue.
+    /// `None` means no cap.
+    pub(crate) lifo_fairness_cap: Option<u8>,
+
+    /// Number of scheduler ticks between polls of the global (inject) queue,
+    /// checked in `Core::next_task` and `ActiveWorker::maintenance`. Lower values
+    /// drain externally-injected work faster, at the cost of more frequent
+    /// contention on the injection queue. Must be nonzero.
+    pub(crate) global_queue_interval: u32,
+
+    /// Throttles how many workers may search for work to steal at once. A
+    /// worker is only allowed to transition into the searching state if
+    /// `searching_workers * steal_throttle_factor < num_workers`, so higher
+    /// values make the inequality harder to satisfy and throttle harder,
+    /// allowing *fewer* simultaneous searchers. The original hardcoded
+    /// behavior ("fewer than half the workers are searching") is
+    /// `steal_throttle_factor == 2`.
+    pub(crate) steal_throttle_factor: u32,
+}
+
+/// Default number of scheduler ticks between polls of the global queue. This
+/// value is fairly arbitrary. I believe this value was copied from golang.
+pub(crate) const DEFAULT_GLOBAL_QUEUE_INTERVAL: u32 = 61;
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            lifo_enabled: true,
+            lifo_fairness_cap: None,
+            global_queue_interval: DEFAULT_GLOBAL_QUEUE_INTERVAL,
+            steal_throttle_factor: 2,
+        }
+    }
+}
+
+impl std::fmt::Debug for Config {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fmt.debug_struct("Config")
+            .field("lifo_enabled", &self.lifo_enabled)
+            .field("lifo_fairness_cap", &self.lifo_fairness_cap)
+            .field("global_queue_interval", &self.global_queue_interval)
+            .field("steal_throttle_factor", &self.steal_throttle_factor)
+            .finish()
+    }
+}
+
 /// Running a task may consume the core. If the core is still available when
 /// running the task completes, it is returned. Otherwise, the worker will need
 /// to stop processing.
@@ -207,7 +311,13 @@ pub(super) fn create(
     handle_inner: HandleInner,
     before_park: Option<Callback>,
     after_unpark: Option<Callback>,
+    config: Config,
 ) -> Arc<Shared> {
+    assert!(
+        config.global_queue_interval > 0,
+        "global_queue_interval must be nonzero"
+    );
+
     let mut cores = Vec::with_capacity(size);
     let mut remotes = Vec::with_capacity(size);
     let mut worker_metrics = Vec::with_capacity(size);
@@ -246,6 +356,8 @@ pub(super) fn create(
         after_unpark,
         scheduler_metrics: SchedulerMetrics::new(),
         worker_metrics: worker_metrics.into_boxed_slice(),
+        config,
+        num_searching: AtomicUsize::new(0),
     });
 
     for (index, core) in cores.drain(..).enumerate() {
@@ -360,12 +472,6 @@ where
     }
 }
 
-/// After how many ticks is the global queue polled. This helps to ensure
-/// fairness.
-///
-/// The number is fairly arbitrary. I believe this value was copied from golang.
-const GLOBAL_POLL_INTERVAL: u8 = 61;
-
 fn run(worker: Arc<Worker>) {
     // Acquire a core. If this fails, then another thread is running this
     // worker and there is nothing further to do.
@@ -442,6 +548,11 @@ impl ActiveWorker {
         coop::budget(|| {
             task.run();
 
+            // Counts how many tasks have been run consecutively out of the
+            // LIFO slot, so a single hot message-passing chain can't
+            // monopolize this worker when a fairness cap is configured.
+            let mut lifo_polls = 0u8;
+
             // As long as there is budget remaining and a task exists in the
             // `lifo_slot`, then keep running.
             loop {
@@ -452,21 +563,28 @@ impl ActiveWorker {
                     None => return Err(()),
                 };
 
-                // Check for a task in the LIFO slot
+                // Check for a task in the LIFO slot.
                 let task = match core.lifo_slot.take() {
                     Some(task) => task,
                     None => return Ok(core),
                 };
 
-                if coop::has_budget_remaining() {
+                let cap_reached = matches!(
+                    self.worker.shared.config.lifo_fairness_cap,
+                    Some(cap) if lifo_polls >= cap
+                );
+
+                if coop::has_budget_remaining() && !cap_reached {
                     // Run the LIFO task, then loop
+                    lifo_polls = lifo_polls.saturating_add(1);
                     core.metrics.incr_poll_count();
                     *self.core.borrow_mut() = Some(core);
                     let task = self.worker.shared.owned.assert_owner(task);
                     task.run();
                 } else {
-                    // Not enough budget left to run the LIFO task, push it to
-                    // the back of the queue and return.
+                    // Not enough budget left to run the LIFO task, or the
+                    // fairness cap was reached. Push it to the back of the
+                    // queue and return.
                     core.run_queue
                         .push_back(task, self.worker.inject(), &mut core.metrics);
                     return Ok(core);
@@ -476,7 +594,7 @@ impl ActiveWorker {
     }
 
     fn maintenance(&self, mut core: Box<Core>) -> Box<Core> {
-        if core.tick % GLOBAL_POLL_INTERVAL == 0 {
+        if core.tick % self.worker.shared.config.global_queue_interval == 0 {
             // Call `park` with a 0 timeout. This enables the I/O driver, timer, ...
             // to run without actually putting the thread to sleep.
             core = self.park_timeout(core, Some(Duration::from_millis(0)));
@@ -563,14 +681,22 @@ impl Core {
 
     /// Return the next notified task available to this worker.
     fn next_task(&mut self, worker: &Worker) -> Option<Notified> {
-        if self.tick % GLOBAL_POLL_INTERVAL == 0 {
-            worker.inject().pop().or_else(|| self.next_local_task())
+        if self.tick % worker.shared.config.global_queue_interval == 0 {
+            worker
+                .inject()
+                .pop()
+                .or_else(|| self.next_local_task(worker))
         } else {
-            self.next_local_task().or_else(|| worker.inject().pop())
+            self.next_local_task(worker)
+                .or_else(|| worker.inject().pop())
         }
     }
 
-    fn next_local_task(&mut self) -> Option<Notified> {
+    fn next_local_task(&mut self, worker: &Worker) -> Option<Notified> {
+        if !worker.shared.config.lifo_enabled {
+            return self.run_queue.pop();
+        }
+
         self.lifo_slot.take().or_else(|| self.run_queue.pop())
     }
 
@@ -597,6 +723,7 @@ impl Core {
             }
 
             let target = &worker.shared.remotes[i];
+
             if let Some(task) = target
                 .steal
                 .steal_into(&mut self.run_queue, &mut self.metrics)
@@ -611,7 +738,16 @@ impl Core {
 
     fn transition_to_searching(&mut self, worker: &Worker) -> bool {
         if !self.is_searching {
+            if !worker.shared.may_start_searching() {
+                return false;
+            }
+
             self.is_searching = worker.shared.idle.transition_worker_to_searching();
+
+            if self.is_searching {
+                // num_searching: entering searching here, keep in sync.
+                worker.shared.num_searching.fetch_add(1, Relaxed);
+            }
         }
 
         self.is_searching
@@ -643,6 +779,11 @@ impl Core {
             .idle
             .transition_worker_to_parked(worker.index, self.is_searching);
 
+        if self.is_searching {
+            // num_searching: leaving searching here, keep in sync.
+            worker.shared.num_searching.fetch_sub(1, Relaxed);
+        }
+
         // The worker is no longer searching. Setting this is the local cache
         // only.
         self.is_searching = false;
@@ -664,6 +805,12 @@ impl Core {
             // is pushed. We do *not* want the worker to transition to "searching"
             // when it wakes when the I/O driver receives new events.
             self.is_searching = !worker.shared.idle.unpark_worker_by_id(worker.index, 0);
+
+            if self.is_searching {
+                // num_searching: entering searching here, keep in sync.
+                worker.shared.num_searching.fetch_add(1, Relaxed);
+            }
+
             return true;
         }
 
@@ -673,6 +820,8 @@ impl Core {
 
         // When unparked, the worker is in the searching state.
         self.is_searching = true;
+        // num_searching: entering searching here, keep in sync.
+        worker.shared.num_searching.fetch_add(1, Relaxed);
         true
     }
 
@@ -703,7 +852,8 @@ impl Core {
         let mut park = self.park.take().expect("park missing");
 
         // Drain the queue
-        while self.next_local_task().is_some() {}
+        self.lifo_slot = None;
+        while self.run_queue.pop().is_some() {}
 
         park.shutdown();
     }
@@ -718,6 +868,11 @@ impl Worker {
     fn activate_from_threadless(me: Arc<Self>, is_searching: bool) -> ActiveWorker {
         let mut core = me.core.take().expect("core missing");
         core.is_searching = is_searching;
+
+        if is_searching {
+            // num_searching: entering searching here, keep in sync.
+            me.shared.num_searching.fetch_add(1, Relaxed);
+        }
         /*
         core
             .park
@@ -856,7 +1011,7 @@ impl Shared {
         // task must always be pushed to the back of the queue, enabling other
         // tasks to be executed. If **not** a yield, then there is more
         // flexibility and the task may go to the front of the queue.
-        let should_notify = if is_yield {
+        let should_notify = if is_yield || !me.config.lifo_enabled {
             core.run_queue
                 .push_back(task, &me.inject, &mut core.metrics);
             true
@@ -923,6 +1078,9 @@ impl Shared {
     }
 
     fn transition_worker_from_searching(me: &Arc<Self>) {
+        // num_searching: leaving searching here, keep in sync.
+        me.num_searching.fetch_sub(1, Relaxed);
+
         if me.idle.transition_worker_from_searching() {
             // We are the final searching worker. Because work was found, we
             // need to notify another worker.
@@ -930,6 +1088,19 @@ impl Shared {
         }
     }
 
+    /// Returns `true` if another worker is allowed to start searching for
+    /// work to steal, per `Config::steal_throttle_factor`. This check is
+    /// purely advisory: it is a plain load with no synchronization with the
+    /// `Idle` state, so a racing caller may occasionally see a stale count.
+    /// That's fine here — worst case a worker searches one round too many or
+    /// too few, which the steal loop already tolerates.
+    fn may_start_searching(&self) -> bool {
+        let num_searching = self.num_searching.load(Relaxed) as u32;
+        let num_workers = self.remotes.len() as u32;
+
+        num_searching * self.config.steal_throttle_factor < num_workers
+    }
+
     /// Signals that a worker has observed the shutdown signal and has replaced
     /// its core back into its handle.
     ///